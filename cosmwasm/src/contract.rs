@@ -0,0 +1,480 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+
+use crate::error::ContractError;
+use crate::helpers::{balance_transfer_msg, split_balance};
+use crate::msg::{
+    ConfigResponse, Cw20HookMsg, EscrowResponse, ExecuteMsg, InstantiateMsg, ListResponse,
+    QueryMsg,
+};
+use crate::state::{Balance, Config, Escrow, Expiration, CONFIG, ESCROWS};
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: info.sender,
+        cw20_token: deps.api.addr_validate(&msg.token)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::EscrowNative {
+            id,
+            recipient,
+            arbiter,
+            end_time,
+            end_height,
+            recipients,
+        } => execute_escrow_native(
+            deps,
+            info,
+            CreateEscrowParams {
+                id,
+                recipient,
+                arbiter,
+                end_time,
+                end_height,
+                recipients,
+            },
+        ),
+        ExecuteMsg::Approve { id } => execute_approve(deps, env, info, id),
+        ExecuteMsg::ApprovePartial { id, amount } => {
+            execute_approve_partial(deps, env, info, id, amount)
+        }
+        ExecuteMsg::Refund { id } => execute_refund(deps, env, info, id),
+        ExecuteMsg::TopUpNative {
+            id,
+            end_time,
+            end_height,
+        } => execute_top_up_native(deps, info, id, end_time, end_height),
+    }
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.cw20_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&wrapper.msg)? {
+        Cw20HookMsg::Create {
+            id,
+            recipient,
+            arbiter,
+            end_time,
+            end_height,
+            recipients,
+        } => {
+            let source = deps.api.addr_validate(&wrapper.sender)?;
+            let balance = Balance::Cw20 {
+                amount: wrapper.amount,
+            };
+            let params = CreateEscrowParams {
+                id,
+                recipient,
+                arbiter,
+                end_time,
+                end_height,
+                recipients,
+            };
+            let (id, escrow) = new_escrow(deps.as_ref(), source, balance, params)?;
+            save_new_escrow(deps, id, escrow)
+        }
+        Cw20HookMsg::TopUp {
+            id,
+            end_time,
+            end_height,
+        } => top_up_escrow(
+            deps,
+            id,
+            Balance::Cw20 {
+                amount: wrapper.amount,
+            },
+            end_time,
+            end_height,
+        ),
+    }
+}
+
+/// Parse the wire-level `end_time`/`end_height` pair into a domain
+/// `Expiration`; exactly one of the two must be set.
+fn parse_expiration(
+    end_time: Option<u64>,
+    end_height: Option<u64>,
+) -> Result<Expiration, ContractError> {
+    match (end_time, end_height) {
+        (Some(t), None) => Ok(Expiration::AtTime(t)),
+        (None, Some(h)) => Ok(Expiration::AtHeight(h)),
+        _ => Err(ContractError::InvalidExpiration {}),
+    }
+}
+
+/// Creation fields shared between `Cw20HookMsg::Create` and
+/// `ExecuteMsg::EscrowNative`, bundled together so the entry points that
+/// build an `Escrow` don't grow a positional parameter per field.
+pub struct CreateEscrowParams {
+    pub id: String,
+    pub recipient: String,
+    pub arbiter: Option<String>,
+    pub end_time: Option<u64>,
+    pub end_height: Option<u64>,
+    pub recipients: Option<Vec<(String, Uint128)>>,
+}
+
+/// Validate `params` against `source`/`balance` and build the `Escrow` to be
+/// saved, returning its id alongside so callers can hand both to
+/// `save_new_escrow`.
+fn new_escrow(
+    deps: Deps,
+    source: cosmwasm_std::Addr,
+    balance: Balance,
+    params: CreateEscrowParams,
+) -> Result<(String, Escrow), ContractError> {
+    let CreateEscrowParams {
+        id,
+        recipient,
+        arbiter,
+        end_time,
+        end_height,
+        recipients,
+    } = params;
+    let escrow = Escrow {
+        source,
+        recipient: deps.api.addr_validate(&recipient)?,
+        arbiter: arbiter.map(|a| deps.api.addr_validate(&a)).transpose()?,
+        balance,
+        expiration: parse_expiration(end_time, end_height)?,
+        recipients: validate_recipients(deps, recipients)?,
+    };
+    Ok((id, escrow))
+}
+
+/// Entry point for depositing native coins directly, mirroring
+/// `Cw20HookMsg::Create` but funded by `info.funds` instead of a cw20 hook.
+pub fn execute_escrow_native(
+    deps: DepsMut,
+    info: MessageInfo,
+    params: CreateEscrowParams,
+) -> Result<Response, ContractError> {
+    let coin = match info.funds.as_slice() {
+        [coin] => coin.clone(),
+        _ => return Err(ContractError::InvalidNativeFunds {}),
+    };
+
+    let balance = Balance::Native {
+        denom: coin.denom,
+        amount: coin.amount,
+    };
+    let (id, escrow) = new_escrow(deps.as_ref(), info.sender, balance, params)?;
+    save_new_escrow(deps, id, escrow)
+}
+
+pub fn execute_top_up_native(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    end_time: Option<u64>,
+    end_height: Option<u64>,
+) -> Result<Response, ContractError> {
+    let coin = match info.funds.as_slice() {
+        [coin] => coin.clone(),
+        _ => return Err(ContractError::InvalidNativeFunds {}),
+    };
+    top_up_escrow(
+        deps,
+        id,
+        Balance::Native {
+            denom: coin.denom,
+            amount: coin.amount,
+        },
+        end_time,
+        end_height,
+    )
+}
+
+/// Merge `deposit` into an existing escrow's balance, bumping its
+/// expiration up to whichever is later so a top-up can never shorten the
+/// lock. `end_time`/`end_height`, if given, must match the kind of bound
+/// the escrow was created with.
+fn top_up_escrow(
+    deps: DepsMut,
+    id: String,
+    deposit: Balance,
+    end_time: Option<u64>,
+    end_height: Option<u64>,
+) -> Result<Response, ContractError> {
+    if deposit.amount().is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut escrow = ESCROWS
+        .may_load(deps.storage, &id)?
+        .ok_or_else(|| ContractError::EscrowNotFound { id: id.clone() })?;
+
+    let new_amount = match (&escrow.balance, &deposit) {
+        (Balance::Cw20 { amount: existing }, Balance::Cw20 { amount: added }) => existing + added,
+        (
+            Balance::Native {
+                denom: existing_denom,
+                amount: existing,
+            },
+            Balance::Native {
+                denom: added_denom,
+                amount: added,
+            },
+        ) if existing_denom == added_denom => existing + added,
+        _ => return Err(ContractError::BalanceMismatch {}),
+    };
+    escrow.balance = escrow.balance.with_amount(new_amount);
+    escrow.expiration = match (escrow.expiration, end_time, end_height) {
+        (Expiration::AtTime(cur), Some(t), None) => Expiration::AtTime(cur.max(t)),
+        (Expiration::AtHeight(cur), None, Some(h)) => Expiration::AtHeight(cur.max(h)),
+        (cur, None, None) => cur,
+        _ => return Err(ContractError::InvalidExpiration {}),
+    };
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "top_up")
+        .add_attribute("id", id)
+        .add_attribute("amount", new_amount))
+}
+
+pub fn execute_approve_partial(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut escrow = ESCROWS
+        .may_load(deps.storage, &id)?
+        .ok_or_else(|| ContractError::EscrowNotFound { id: id.clone() })?;
+
+    if !escrow.recipients.is_empty() {
+        return Err(ContractError::PartialApproveNotSupported {});
+    }
+
+    let expired = escrow.expiration.is_expired(&env.block);
+    let is_arbiter = escrow.arbiter.as_ref() == Some(&info.sender);
+    if !is_arbiter && !expired {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let remaining = escrow
+        .balance
+        .amount()
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientBalance {
+            amount,
+            balance: escrow.balance.amount(),
+        })?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let msg = balance_transfer_msg(
+        &escrow.balance.with_amount(amount),
+        config.cw20_token.as_str(),
+        escrow.recipient.as_str(),
+    )?;
+
+    if remaining.is_zero() {
+        ESCROWS.remove(deps.storage, &id);
+    } else {
+        escrow.balance = escrow.balance.with_amount(remaining);
+        ESCROWS.save(deps.storage, &id, &escrow)?;
+    }
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "approve_partial")
+        .add_attribute("id", id)
+        .add_attribute("amount", amount))
+}
+
+/// Validate an optional weighted-split recipient list. `None` means the
+/// escrow pays `recipient` in full on approval, as before; `Some(list)`
+/// must be non-empty with a positive total weight.
+fn validate_recipients(
+    deps: cosmwasm_std::Deps,
+    recipients: Option<Vec<(String, Uint128)>>,
+) -> Result<Vec<(cosmwasm_std::Addr, Uint128)>, ContractError> {
+    let recipients = match recipients {
+        None => return Ok(vec![]),
+        Some(recipients) => recipients,
+    };
+    if recipients.is_empty() {
+        return Err(ContractError::EmptyRecipients {});
+    }
+    let total_weight = recipients
+        .iter()
+        .map(|(_, w)| *w)
+        .fold(Uint128::zero(), |a, b| a + b);
+    if total_weight.is_zero() {
+        return Err(ContractError::ZeroTotalWeight {});
+    }
+    recipients
+        .into_iter()
+        .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
+        .collect()
+}
+
+fn save_new_escrow(
+    deps: DepsMut,
+    id: String,
+    escrow: Escrow,
+) -> Result<Response, ContractError> {
+    if escrow.balance.amount().is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if ESCROWS.has(deps.storage, &id) {
+        return Err(ContractError::EscrowExists { id });
+    }
+
+    let amount = escrow.balance.amount();
+    ESCROWS.save(deps.storage, &id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "escrow")
+        .add_attribute("id", id)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let escrow = ESCROWS
+        .may_load(deps.storage, &id)?
+        .ok_or_else(|| ContractError::EscrowNotFound { id: id.clone() })?;
+
+    let expired = escrow.expiration.is_expired(&env.block);
+    let is_arbiter = escrow.arbiter.as_ref() == Some(&info.sender);
+    if !is_arbiter && !expired {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    ESCROWS.remove(deps.storage, &id);
+
+    let messages = if escrow.recipients.is_empty() {
+        vec![balance_transfer_msg(
+            &escrow.balance,
+            config.cw20_token.as_str(),
+            escrow.recipient.as_str(),
+        )?]
+    } else {
+        split_balance(&escrow.balance, &escrow.recipients)
+            .into_iter()
+            .map(|(addr, share)| {
+                balance_transfer_msg(&share, config.cw20_token.as_str(), addr.as_str())
+            })
+            .collect::<StdResult<Vec<_>>>()?
+    };
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "approve")
+        .add_attribute("id", id))
+}
+
+pub fn execute_refund(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let escrow = ESCROWS
+        .may_load(deps.storage, &id)?
+        .ok_or_else(|| ContractError::EscrowNotFound { id: id.clone() })?;
+
+    if !escrow.expiration.is_expired(&env.block) {
+        return Err(ContractError::NotExpired {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    ESCROWS.remove(deps.storage, &id);
+
+    let msg = balance_transfer_msg(
+        &escrow.balance,
+        config.cw20_token.as_str(),
+        escrow.source.as_str(),
+    )?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "refund")
+        .add_attribute("id", id))
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Escrow { id } => to_binary(&query_escrow(deps, id)?),
+        QueryMsg::List {} => to_binary(&query_list(deps)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        token: config.cw20_token.to_string(),
+    })
+}
+
+fn query_escrow(deps: Deps, id: String) -> StdResult<EscrowResponse> {
+    let escrow = ESCROWS.load(deps.storage, &id)?;
+    let denom = match &escrow.balance {
+        Balance::Native { denom, .. } => Some(denom.clone()),
+        Balance::Cw20 { .. } => None,
+    };
+    let (end_time, end_height) = match escrow.expiration {
+        Expiration::AtTime(t) => (Some(t), None),
+        Expiration::AtHeight(h) => (None, Some(h)),
+    };
+    Ok(EscrowResponse {
+        source: escrow.source,
+        recipient: escrow.recipient,
+        arbiter: escrow.arbiter,
+        amount: escrow.balance.amount(),
+        denom,
+        end_time,
+        end_height,
+        recipients: escrow.recipients,
+    })
+}
+
+fn query_list(deps: Deps) -> StdResult<ListResponse> {
+    let ids = ESCROWS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListResponse { ids })
+}
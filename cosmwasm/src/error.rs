@@ -0,0 +1,44 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Escrow id already exists: {id}")]
+    EscrowExists { id: String },
+
+    #[error("No escrow found for id: {id}")]
+    EscrowNotFound { id: String },
+
+    #[error("Escrow has not expired yet")]
+    NotExpired {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Must attach exactly one native coin")]
+    InvalidNativeFunds {},
+
+    #[error("Recipients list must not be empty")]
+    EmptyRecipients {},
+
+    #[error("Total recipient weight must be greater than zero")]
+    ZeroTotalWeight {},
+
+    #[error("Approve amount {amount} exceeds escrowed balance {balance}")]
+    InsufficientBalance { amount: Uint128, balance: Uint128 },
+
+    #[error("Partial approval is not supported for weighted-split escrows")]
+    PartialApproveNotSupported {},
+
+    #[error("Top-up denomination does not match the escrow's existing balance")]
+    BalanceMismatch {},
+
+    #[error("Exactly one of end_time or end_height must be set")]
+    InvalidExpiration {},
+}
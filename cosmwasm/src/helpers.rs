@@ -0,0 +1,60 @@
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use crate::state::Balance;
+
+/// Build the submessage that pays an escrow's `balance` out of this contract
+/// to `recipient`, using a cw20 `Transfer` or a native `BankMsg::Send`
+/// depending on how the escrow was funded.
+pub fn balance_transfer_msg(
+    balance: &Balance,
+    cw20_token: &str,
+    recipient: &str,
+) -> StdResult<CosmosMsg> {
+    match balance {
+        Balance::Cw20 { amount } => Ok(WasmMsg::Execute {
+            contract_addr: cw20_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: *amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+        Balance::Native { denom, amount } => Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount: *amount,
+            }],
+        }
+        .into()),
+    }
+}
+
+/// Split `balance` across `weights` using integer math, assigning the
+/// truncation remainder to the first recipient so the shares sum back to
+/// exactly `balance`'s amount. Callers must ensure `weights` is non-empty
+/// and its total is non-zero.
+pub fn split_balance(balance: &Balance, weights: &[(Addr, Uint128)]) -> Vec<(Addr, Balance)> {
+    let total_amount = balance.amount();
+    let total_weight = weights.iter().map(|(_, w)| *w).fold(Uint128::zero(), |a, b| a + b);
+
+    let mut shares: Vec<(Addr, Balance)> = weights
+        .iter()
+        .map(|(addr, weight)| {
+            let share = total_amount.multiply_ratio(*weight, total_weight);
+            (addr.clone(), balance.with_amount(share))
+        })
+        .collect();
+
+    let distributed = shares
+        .iter()
+        .map(|(_, b)| b.amount())
+        .fold(Uint128::zero(), |a, b| a + b);
+    let remainder = total_amount - distributed;
+    if let Some((_, first)) = shares.first_mut() {
+        *first = first.with_amount(first.amount() + remainder);
+    }
+    shares
+}
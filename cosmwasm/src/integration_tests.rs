@@ -0,0 +1,145 @@
+#![cfg(test)]
+
+use cosmwasm_std::{to_binary, Addr, Uint128};
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use cw_multi_test::{App, Executor};
+
+use crate::msg::{Cw20HookMsg, ExecuteMsg, ListResponse, QueryMsg};
+use crate::test_utils::setup_cw20_and_escrow;
+
+#[test]
+fn arbiter_can_approve_before_expiration() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let arbiter = Addr::unchecked("arbiter");
+
+    let mut router = App::new(|_, _, _| {});
+
+    let (usdc_addr, escrow_addr) = setup_cw20_and_escrow(
+        &mut router,
+        &owner,
+        vec![Cw20Coin {
+            address: alice.to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: escrow_addr.to_string(),
+        amount: Uint128::from(250u128),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: "deal-1".to_string(),
+            recipient: bob.to_string(),
+            arbiter: Some(arbiter.to_string()),
+            end_time: Some(u64::MAX),
+            end_height: None,
+            recipients: None,
+        })
+        .unwrap(),
+    };
+    router
+        .execute_contract(alice.clone(), usdc_addr.clone(), &msg, &[])
+        .unwrap();
+
+    let res: ListResponse = router
+        .wrap()
+        .query_wasm_smart(escrow_addr.clone(), &QueryMsg::List {})
+        .unwrap();
+    assert_eq!(res.ids, vec!["deal-1".to_string()]);
+
+    router
+        .execute_contract(
+            arbiter,
+            escrow_addr,
+            &ExecuteMsg::Approve {
+                id: "deal-1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res: cw20::BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            usdc_addr,
+            &cw20::Cw20QueryMsg::Balance {
+                address: bob.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::from(250u128));
+}
+
+#[test]
+fn approve_splits_payout_by_weight() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+
+    let mut router = App::new(|_, _, _| {});
+
+    let (usdc_addr, escrow_addr) = setup_cw20_and_escrow(
+        &mut router,
+        &owner,
+        vec![Cw20Coin {
+            address: alice.to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+
+    // 100 split 1:2 between bob and carol; truncation leaves a remainder of 1,
+    // which should land on bob as the first recipient.
+    let msg = Cw20ExecuteMsg::Send {
+        contract: escrow_addr.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: "split".to_string(),
+            recipient: bob.to_string(),
+            arbiter: None,
+            end_time: Some(0),
+            end_height: None,
+            recipients: Some(vec![
+                (bob.to_string(), Uint128::from(1u128)),
+                (carol.to_string(), Uint128::from(2u128)),
+            ]),
+        })
+        .unwrap(),
+    };
+    router
+        .execute_contract(alice.clone(), usdc_addr.clone(), &msg, &[])
+        .unwrap();
+
+    router
+        .execute_contract(
+            alice,
+            escrow_addr,
+            &ExecuteMsg::Approve {
+                id: "split".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let bob_balance: cw20::BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            usdc_addr.clone(),
+            &cw20::Cw20QueryMsg::Balance {
+                address: bob.to_string(),
+            },
+        )
+        .unwrap();
+    let carol_balance: cw20::BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            usdc_addr,
+            &cw20::Cw20QueryMsg::Balance {
+                address: carol.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(bob_balance.balance, Uint128::from(34u128));
+    assert_eq!(carol_balance.balance, Uint128::from(66u128));
+}
@@ -0,0 +1,11 @@
+pub mod contract;
+mod error;
+pub mod helpers;
+pub mod msg;
+pub mod state;
+mod unit_tests;
+mod integration_tests;
+mod property_tests;
+mod test_utils;
+
+pub use crate::error::ContractError;
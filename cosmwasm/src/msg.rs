@@ -0,0 +1,110 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Entry point for cw20's Send/Receive hook; the wrapped `Cw20HookMsg`
+    /// decides what to do with the attached funds.
+    Receive(Cw20ReceiveMsg),
+    /// Open a new escrow funded by the native coins sent alongside this
+    /// message, mirroring `Cw20HookMsg::Create` for non-cw20 deposits.
+    EscrowNative {
+        id: String,
+        recipient: String,
+        arbiter: Option<String>,
+        /// Exactly one of `end_time` (seconds) or `end_height` must be set.
+        end_time: Option<u64>,
+        end_height: Option<u64>,
+        /// Relative weights for splitting the payout across several
+        /// addresses on approval, instead of paying `recipient` in full.
+        #[serde(default)]
+        recipients: Option<Vec<(String, Uint128)>>,
+    },
+    /// Release an escrow's funds to its recipient. Callable by the arbiter
+    /// at any time, or by anyone once the escrow has expired.
+    Approve { id: String },
+    /// Release only part of an escrow's balance to its recipient, leaving
+    /// the rest in place. Not available for weighted-split escrows.
+    ApprovePartial { id: String, amount: Uint128 },
+    /// Return an escrow's funds to its source. Only possible once expired.
+    Refund { id: String },
+    /// Add native coins to an already-open escrow, optionally pushing out
+    /// its unlock bound. Must match the kind of bound the escrow already
+    /// uses (time or height), and can only ever move later, never earlier.
+    TopUpNative {
+        id: String,
+        end_time: Option<u64>,
+        end_height: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Open a new escrow under `id`, funded by the amount sent alongside this
+    /// message.
+    Create {
+        id: String,
+        recipient: String,
+        arbiter: Option<String>,
+        /// Exactly one of `end_time` (seconds) or `end_height` must be set.
+        end_time: Option<u64>,
+        end_height: Option<u64>,
+        /// Relative weights for splitting the payout across several
+        /// addresses on approval, instead of paying `recipient` in full.
+        #[serde(default)]
+        recipients: Option<Vec<(String, Uint128)>>,
+    },
+    /// Add more of the configured cw20 token to an already-open escrow,
+    /// optionally pushing out its unlock bound. Must match the kind of
+    /// bound the escrow already uses (time or height).
+    TopUp {
+        id: String,
+        end_time: Option<u64>,
+        end_height: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Escrow { id: String },
+    List {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowResponse {
+    pub source: Addr,
+    pub recipient: Addr,
+    pub arbiter: Option<Addr>,
+    pub amount: Uint128,
+    /// `Some(denom)` for a native-coin escrow, `None` for a cw20 escrow.
+    pub denom: Option<String>,
+    /// Exactly one of `end_time`/`end_height` is set, matching however
+    /// this escrow's expiration was expressed at creation.
+    pub end_time: Option<u64>,
+    pub end_height: Option<u64>,
+    /// Non-empty when approval splits the payout by weight instead of
+    /// paying `recipient` in full.
+    pub recipients: Vec<(Addr, Uint128)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListResponse {
+    pub ids: Vec<String>,
+}
@@ -1,29 +1,18 @@
-//
-// unit testing imports
-//
-// use crate::contract::{instantiate, query};
-// use crate::msg::{ConfigResponse, InstantiateMsg, QueryMsg};
-
-use cosmwasm_std::from_binary;
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+#![cfg(test)]
 
 //
-// integration test imports
+// Property testing
 //
-use cosmwasm_std::{to_binary, Addr, Empty, Uint128};
-use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
-use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cosmwasm_std::{to_binary, Addr, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Executor};
+use proptest::prelude::*;
 
 use crate::{
-    contract::{execute, instantiate, query},
-    msg::{ConfigResponse, Cw20HookMsg, EscrowResponse, ExecuteMsg, InstantiateMsg, QueryMsg},
+    msg::{ConfigResponse, Cw20HookMsg, EscrowResponse, ExecuteMsg, QueryMsg},
+    test_utils::setup_cw20_and_escrow,
 };
 
-//
-// Property testing
-//
-use proptest::prelude::*;
-
 macro_rules! some_money_amount {
     () => {
         0..999999999999999u128
@@ -51,14 +40,135 @@ proptest! {
             };
 
         prop_assume!(sent_amount != 0); // Invalid zero amount.
-        escrow_redeem_is_always_equal_to_send_amount(addrs, initial_balances, sent_amount, escrow_time);
+        escrow_approve_is_always_equal_to_send_amount(addrs, initial_balances, sent_amount, escrow_time);
+    }
+
+    #[test]
+    fn partial_approvals_never_overdraw_the_escrow(
+        addrs in vec!["[:ascii:]{3,}", "[:ascii:]{3,}", "[:ascii:]{3,}"],
+        deposit in 1..999999999999999u128,
+        chunks in vec![0..999999999999999u128; 4],
+    ) {
+        prop_assume!(addrs[0] != addrs[1]);
+        prop_assume!(addrs[1] != addrs[2]);
+        prop_assume!(addrs[0] != addrs[2]);
+
+        partial_redemptions_never_exceed_deposit(addrs, deposit, chunks);
+    }
+
+    #[test]
+    fn refund_always_returns_the_full_deposit_once_expired(
+        addrs in vec!["[:ascii:]{3,}", "[:ascii:]{3,}", "[:ascii:]{3,}"],
+        deposit in 1..999999999999999u128,
+        escrow_time in 10..99999u64,
+    ) {
+        prop_assume!(addrs[0] != addrs[1]);
+        prop_assume!(addrs[1] != addrs[2]);
+        prop_assume!(addrs[0] != addrs[2]);
+
+        refund_after_expiration_returns_full_deposit(addrs, deposit, escrow_time);
     }
 }
 
-fn escrow_redeem_is_always_equal_to_send_amount(
+/// For any sequence of `ApprovePartial` calls, the running total withdrawn
+/// must never exceed the original deposit, and the escrow's remaining
+/// balance must always equal `deposit - withdrawn` until it is fully drained
+/// and removed.
+fn partial_redemptions_never_exceed_deposit(
     addrs: Vec<String>,
-    initial_balances: Vec<u128>,
-    sent_amount: u128,
+    deposit: u128,
+    chunks: Vec<u128>,
+) {
+    let owner = Addr::unchecked(addrs[0].clone());
+    let alice = Addr::unchecked(addrs[1].clone());
+    let bob = Addr::unchecked(addrs[2].clone());
+
+    let mut router: App = App::new(|_, _, _| {});
+
+    let (usdc_addr, escrow_addr) = setup_cw20_and_escrow(
+        &mut router,
+        &owner,
+        vec![Cw20Coin {
+            address: alice.to_string(),
+            amount: Uint128::from(deposit),
+        }],
+    );
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: escrow_addr.to_string(),
+        amount: Uint128::from(deposit),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: "deal".to_string(),
+            recipient: bob.to_string(),
+            arbiter: None,
+            // already expired, so anyone may approve without an arbiter
+            end_time: Some(0),
+            end_height: None,
+            recipients: None,
+        })
+        .unwrap(),
+    };
+    router
+        .execute_contract(alice, usdc_addr.clone(), &msg, &[])
+        .unwrap();
+
+    let mut withdrawn = Uint128::zero();
+    for chunk in chunks {
+        let remaining = Uint128::from(deposit) - withdrawn;
+        if remaining.is_zero() {
+            break;
+        }
+        // Clamp each attempt to what's left, so a chunk larger than the
+        // remaining balance becomes one that exactly drains the escrow.
+        let amount = Uint128::from(chunk).min(remaining);
+        if amount.is_zero() {
+            continue;
+        }
+
+        router
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                escrow_addr.clone(),
+                &ExecuteMsg::ApprovePartial {
+                    id: "deal".to_string(),
+                    amount,
+                },
+                &[],
+            )
+            .unwrap();
+        withdrawn += amount;
+
+        let bob_balance: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                usdc_addr.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: bob.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(bob_balance.balance, withdrawn);
+        assert!(withdrawn <= Uint128::from(deposit));
+
+        let escrow_balance: BalanceResponse = router
+            .wrap()
+            .query_wasm_smart(
+                usdc_addr.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: escrow_addr.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(escrow_balance.balance, Uint128::from(deposit) - withdrawn);
+    }
+}
+
+/// A refund must always fail before expiration and, once the escrow has
+/// expired, must return exactly the original deposit to the source and
+/// leave the contract holding nothing for that id.
+fn refund_after_expiration_returns_full_deposit(
+    addrs: Vec<String>,
+    deposit: u128,
     escrow_time: u64,
 ) {
     let owner = Addr::unchecked(addrs[0].clone());
@@ -67,54 +177,111 @@ fn escrow_redeem_is_always_equal_to_send_amount(
 
     let mut router: App = App::new(|_, _, _| {});
 
-    // upload the contracts
-    let escrow_id = router.store_code(contract_escrow());
-    let usdc_id = router.store_code(contract_cw20());
-
-    // instantiate the contracts
-    let usdc_addr = router
-        .instantiate_contract(
-            usdc_id,
-            owner.clone(),
-            &cw20_base::msg::InstantiateMsg {
-                name: "USDC".to_string(),
-                symbol: "USDC".to_string(),
-                decimals: 9, //see here
-                initial_balances: vec![
-                    Cw20Coin {
-                        address: alice.to_string(),
-                        amount: Uint128::from(initial_balances[0]),
-                    },
-                    Cw20Coin {
-                        address: bob.to_string(),
-                        amount: Uint128::from(initial_balances[1]),
-                    },
-                ],
-                mint: Some(MinterResponse {
-                    minter: owner.to_string(),
-                    cap: None,
-                }),
-                marketing: None,
+    let (usdc_addr, escrow_addr) = setup_cw20_and_escrow(
+        &mut router,
+        &owner,
+        vec![Cw20Coin {
+            address: alice.to_string(),
+            amount: Uint128::from(deposit),
+        }],
+    );
+
+    let start_time = router.block_info().time.seconds();
+    let msg = Cw20ExecuteMsg::Send {
+        contract: escrow_addr.to_string(),
+        amount: Uint128::from(deposit),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: "deal".to_string(),
+            recipient: bob.to_string(),
+            arbiter: None,
+            end_time: Some(start_time + escrow_time),
+            end_height: None,
+            recipients: None,
+        })
+        .unwrap(),
+    };
+    router
+        .execute_contract(alice.clone(), usdc_addr.clone(), &msg, &[])
+        .unwrap();
+
+    // refund before expiration always fails, regardless of caller
+    router
+        .execute_contract(
+            alice.clone(),
+            escrow_addr.clone(),
+            &ExecuteMsg::Refund {
+                id: "deal".to_string(),
             },
             &[],
-            "cw20",
-            None,
         )
-        .unwrap();
+        .unwrap_err();
 
-    let escrow_addr = router
-        .instantiate_contract(
-            escrow_id,
-            owner.clone(),
-            &InstantiateMsg {
-                token: usdc_addr.to_string(),
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(escrow_time);
+        block.height += 1;
+    });
+
+    router
+        .execute_contract(
+            alice.clone(),
+            escrow_addr.clone(),
+            &ExecuteMsg::Refund {
+                id: "deal".to_string(),
             },
             &[],
-            "engine",
-            None,
         )
         .unwrap();
 
+    let alice_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            usdc_addr.clone(),
+            &Cw20QueryMsg::Balance {
+                address: alice.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(alice_balance.balance, Uint128::from(deposit));
+
+    let escrow_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            usdc_addr,
+            &Cw20QueryMsg::Balance {
+                address: escrow_addr.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(escrow_balance.balance, Uint128::zero());
+}
+
+fn escrow_approve_is_always_equal_to_send_amount(
+    addrs: Vec<String>,
+    initial_balances: Vec<u128>,
+    sent_amount: u128,
+    escrow_time: u64,
+) {
+    let owner = Addr::unchecked(addrs[0].clone());
+    let alice = Addr::unchecked(addrs[1].clone());
+    let bob = Addr::unchecked(addrs[2].clone());
+
+    let mut router: App = App::new(|_, _, _| {});
+
+    let (usdc_addr, escrow_addr) = setup_cw20_and_escrow(
+        &mut router,
+        &owner,
+        vec![
+            Cw20Coin {
+                address: alice.to_string(),
+                amount: Uint128::from(initial_balances[0]),
+            },
+            Cw20Coin {
+                address: bob.to_string(),
+                amount: Uint128::from(initial_balances[1]),
+            },
+        ],
+    );
+
     // validate the config
     let msg = QueryMsg::Config {};
     let res: ConfigResponse = router
@@ -124,11 +291,19 @@ fn escrow_redeem_is_always_equal_to_send_amount(
     assert_eq!(res.owner, owner);
     assert_eq!(res.token, usdc_addr.to_string());
 
-    // escrow funds into the contract
+    // alice escrows funds into the contract, for bob to redeem, with no arbiter
     let msg = Cw20ExecuteMsg::Send {
         contract: escrow_addr.to_string(),
         amount: Uint128::from(sent_amount),
-        msg: to_binary(&Cw20HookMsg::Escrow { time: escrow_time }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: "deal".to_string(),
+            recipient: bob.to_string(),
+            arbiter: None,
+            end_time: Some(1571797419u64 + escrow_time),
+            end_height: None,
+            recipients: None,
+        })
+        .unwrap(),
     };
 
     let res = router
@@ -136,7 +311,7 @@ fn escrow_redeem_is_always_equal_to_send_amount(
         .unwrap();
     assert_eq!("escrow", res.events[3].attributes[1].value);
 
-    // duplicate escrow should fail
+    // reusing the same id should fail
     router
         .execute_contract(alice.clone(), usdc_addr.clone(), &msg, &[])
         .unwrap_err();
@@ -152,19 +327,21 @@ fn escrow_redeem_is_always_equal_to_send_amount(
     assert_eq!(res.balance, Uint128::from(sent_amount));
 
     let msg = QueryMsg::Escrow {
-        address: alice.to_string(),
+        id: "deal".to_string(),
     };
     let res: EscrowResponse = router
         .wrap()
         .query_wasm_smart(escrow_addr.clone(), &msg)
         .unwrap();
     assert_eq!(res.amount, Uint128::from(sent_amount));
-    assert_eq!(res.time, 1571797419u64 + escrow_time);
+    assert_eq!(res.end_time, Some(1571797419u64 + escrow_time));
 
-    // redeem funds from the escrow
-    let msg = ExecuteMsg::Redeem {};
+    // approve the escrow, releasing funds to bob
+    let msg = ExecuteMsg::Approve {
+        id: "deal".to_string(),
+    };
 
-    // should fail as block has not moved
+    // should fail as block has not moved and there's no arbiter to approve early
     router
         .execute_contract(alice.clone(), escrow_addr.clone(), &msg, &[])
         .unwrap_err();
@@ -178,17 +355,20 @@ fn escrow_redeem_is_always_equal_to_send_amount(
     let res = router
         .execute_contract(alice.clone(), escrow_addr.clone(), &msg, &[])
         .unwrap();
-    assert_eq!("redeem", res.events[1].attributes[1].value);
+    assert_eq!("approve", res.events[1].attributes[1].value);
 
-    // check alice balance
+    // check bob balance
     let msg = Cw20QueryMsg::Balance {
-        address: alice.to_string(),
+        address: bob.to_string(),
     };
     let res: BalanceResponse = router
         .wrap()
         .query_wasm_smart(usdc_addr.clone(), &msg)
         .unwrap();
-    assert_eq!(res.balance, Uint128::from(initial_balances[0]));
+    assert_eq!(
+        res.balance,
+        Uint128::from(initial_balances[1]) + Uint128::from(sent_amount)
+    );
 
     // check contract balance
     let msg = Cw20QueryMsg::Balance {
@@ -200,17 +380,3 @@ fn escrow_redeem_is_always_equal_to_send_amount(
         .unwrap();
     assert_eq!(res.balance, Uint128::zero());
 }
-
-fn contract_cw20() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new_with_empty(
-        cw20_base::contract::execute,
-        cw20_base::contract::instantiate,
-        cw20_base::contract::query,
-    );
-    Box::new(contract)
-}
-
-fn contract_escrow() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new_with_empty(execute, instantiate, query);
-    Box::new(contract)
-}
@@ -0,0 +1,76 @@
+use cosmwasm_std::{Addr, BlockInfo, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub cw20_token: Addr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// The funds locked in an escrow: either native coins or the cw20 token
+/// configured for this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum Balance {
+    Cw20 { amount: Uint128 },
+    Native { denom: String, amount: Uint128 },
+}
+
+impl Balance {
+    pub fn amount(&self) -> Uint128 {
+        match self {
+            Balance::Cw20 { amount } => *amount,
+            Balance::Native { amount, .. } => *amount,
+        }
+    }
+
+    /// Same denomination/token, but carrying `amount` instead.
+    pub fn with_amount(&self, amount: Uint128) -> Balance {
+        match self {
+            Balance::Cw20 { .. } => Balance::Cw20 { amount },
+            Balance::Native { denom, .. } => Balance::Native {
+                denom: denom.clone(),
+                amount,
+            },
+        }
+    }
+}
+
+/// The bound after which an escrow can be approved or refunded by anyone,
+/// expressed either in wall-clock time or block height.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum Expiration {
+    AtTime(u64),
+    AtHeight(u64),
+}
+
+impl Expiration {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            Expiration::AtTime(t) => block.time.seconds() >= *t,
+            Expiration::AtHeight(h) => block.height >= *h,
+        }
+    }
+}
+
+/// A single conditional payment: `source` deposited `balance`, which is
+/// released to `recipient` either by `arbiter` approval or, after
+/// `expiration`, by anyone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Escrow {
+    pub source: Addr,
+    pub recipient: Addr,
+    pub arbiter: Option<Addr>,
+    pub balance: Balance,
+    pub expiration: Expiration,
+    /// Relative weights to split the balance across on approval. Empty
+    /// means the full balance goes to `recipient`.
+    pub recipients: Vec<(Addr, Uint128)>,
+}
+
+/// Open escrows keyed by caller-chosen id, so a sender can hold more than one
+/// at a time.
+pub const ESCROWS: Map<&str, Escrow> = Map::new("escrows");
@@ -0,0 +1,70 @@
+#![cfg(test)]
+
+//! Shared `cw_multi_test` fixtures for the integration and property tests.
+
+use cosmwasm_std::{Addr, Empty};
+use cw20::{Cw20Coin, MinterResponse};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::contract::{execute, instantiate, query};
+use crate::msg::InstantiateMsg;
+
+pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+pub fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+/// Store and instantiate a cw20 token owned by `owner` and seeded with
+/// `initial_balances`, plus an escrow contract configured to use it.
+/// Returns `(usdc_addr, escrow_addr)`.
+pub fn setup_cw20_and_escrow(
+    router: &mut App,
+    owner: &Addr,
+    initial_balances: Vec<Cw20Coin>,
+) -> (Addr, Addr) {
+    let cw20_id = router.store_code(contract_cw20());
+    let escrow_id = router.store_code(contract_escrow());
+
+    let usdc_addr = router
+        .instantiate_contract(
+            cw20_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: "USDC".to_string(),
+                symbol: "USDC".to_string(),
+                decimals: 9,
+                initial_balances,
+                mint: Some(MinterResponse {
+                    minter: owner.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            },
+            &[],
+            "cw20",
+            None,
+        )
+        .unwrap();
+
+    let escrow_addr = router
+        .instantiate_contract(
+            escrow_id,
+            owner.clone(),
+            &InstantiateMsg {
+                token: usdc_addr.to_string(),
+            },
+            &[],
+            "engine",
+            None,
+        )
+        .unwrap();
+
+    (usdc_addr, escrow_addr)
+}
@@ -0,0 +1,426 @@
+#![cfg(test)]
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{coin, coins, from_binary, to_binary};
+use cw20::Cw20ReceiveMsg;
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, Cw20HookMsg, EscrowResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+const CW20_TOKEN: &str = "cw20token";
+
+fn instantiate_escrow(deps: cosmwasm_std::DepsMut) {
+    let msg = InstantiateMsg {
+        token: CW20_TOKEN.to_string(),
+    };
+    let info = mock_info("owner", &[]);
+    instantiate(deps, mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn proper_instantiate() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.owner, "owner");
+    assert_eq!(res.token, CW20_TOKEN);
+}
+
+fn create_msg(id: &str, recipient: &str, arbiter: Option<&str>, end_time: u64) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "source".to_string(),
+        amount: 100u128.into(),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: id.to_string(),
+            recipient: recipient.to_string(),
+            arbiter: arbiter.map(|s| s.to_string()),
+            end_time: Some(end_time),
+            end_height: None,
+            recipients: None,
+        })
+        .unwrap(),
+    })
+}
+
+#[test]
+fn duplicate_id_rejected() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let info = mock_info(CW20_TOKEN, &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        create_msg("deal-1", "recipient", None, 1_000),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg("deal-1", "recipient", None, 1_000),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::EscrowExists { .. }));
+}
+
+#[test]
+fn refund_before_expiration_fails() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let info = mock_info(CW20_TOKEN, &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg("deal-1", "recipient", None, u64::MAX),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Refund {
+            id: "deal-1".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotExpired {}));
+}
+
+#[test]
+fn escrow_native_stores_attached_coin() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(1),
+            end_height: None,
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    let res: EscrowResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Escrow {
+                id: "deal-1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, 100u128.into());
+    assert_eq!(res.denom, Some("uatom".to_string()));
+}
+
+#[test]
+fn escrow_native_rejects_multiple_coins() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &[coin(100, "uatom"), coin(50, "uosmo")]),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(1),
+            end_height: None,
+            recipients: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidNativeFunds {}));
+}
+
+#[test]
+fn approve_partial_leaves_remainder_open() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let info = mock_info(CW20_TOKEN, &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        create_msg("deal-1", "recipient", None, 0),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::ApprovePartial {
+            id: "deal-1".to_string(),
+            amount: 40u128.into(),
+        },
+    )
+    .unwrap();
+
+    let res: EscrowResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Escrow {
+                id: "deal-1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, 60u128.into());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::ApprovePartial {
+            id: "deal-1".to_string(),
+            amount: 1000u128.into(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+}
+
+#[test]
+fn top_up_native_adds_amount_and_extends_end_time() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(10),
+            end_height: None,
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(50, "uatom")),
+        ExecuteMsg::TopUpNative {
+            id: "deal-1".to_string(),
+            end_time: Some(20),
+            end_height: None,
+        },
+    )
+    .unwrap();
+
+    let res: EscrowResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Escrow {
+                id: "deal-1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.amount, 150u128.into());
+    assert_eq!(res.end_time, Some(20));
+}
+
+#[test]
+fn top_up_native_rejects_mismatched_denom() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(10),
+            end_height: None,
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(50, "uosmo")),
+        ExecuteMsg::TopUpNative {
+            id: "deal-1".to_string(),
+            end_time: None,
+            end_height: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::BalanceMismatch {}));
+}
+
+#[test]
+fn top_up_cw20_rejects_a_native_escrow() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(10),
+            end_height: None,
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(CW20_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "source".to_string(),
+            amount: 50u128.into(),
+            msg: to_binary(&Cw20HookMsg::TopUp {
+                id: "deal-1".to_string(),
+                end_time: None,
+                end_height: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::BalanceMismatch {}));
+}
+
+#[test]
+fn height_based_escrow_tracks_block_height() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: None,
+            end_height: Some(mock_env().block.height + 10),
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    let res: EscrowResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Escrow {
+                id: "deal-1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.end_time, None);
+    assert_eq!(res.end_height, Some(mock_env().block.height + 10));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Refund {
+            id: "deal-1".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::NotExpired {}));
+}
+
+#[test]
+fn top_up_rejects_a_bound_kind_that_does_not_match_the_escrow() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: None,
+            end_height: Some(mock_env().block.height + 10),
+            recipients: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(50, "uatom")),
+        ExecuteMsg::TopUpNative {
+            id: "deal-1".to_string(),
+            end_time: Some(20),
+            end_height: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidExpiration {}));
+}
+
+#[test]
+fn create_requires_exactly_one_expiration_kind() {
+    let mut deps = mock_dependencies();
+    instantiate_escrow(deps.as_mut());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &coins(100, "uatom")),
+        ExecuteMsg::EscrowNative {
+            id: "deal-1".to_string(),
+            recipient: "recipient".to_string(),
+            arbiter: None,
+            end_time: Some(1),
+            end_height: Some(1),
+            recipients: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidExpiration {}));
+}